@@ -0,0 +1,65 @@
+//! Converting values read off disk, in whatever byte order HDF5 stored
+//! them, into the host's native byte order.
+
+/// Byte order a value was stored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    BE,
+    LE,
+}
+
+/// Swap every element of a slice into the host's native byte order, in
+/// place, if it is not already.
+pub trait ToNative {
+    fn to_native(&mut self, order: Order);
+}
+
+macro_rules! impl_to_native {
+    ($($t:ty),*) => {
+        $(
+            impl ToNative for [$t] {
+                fn to_native(&mut self, order: Order) {
+                    let swap = match order {
+                        Order::BE => cfg!(target_endian = "little"),
+                        Order::LE => cfg!(target_endian = "big"),
+                    };
+
+                    if swap {
+                        for v in self.iter_mut() {
+                            *v = <$t>::from_ne_bytes(v.to_ne_bytes().iter().rev().cloned().collect::<Vec<_>>().try_into().unwrap());
+                        }
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_to_native!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swap_u16() {
+        let mut v = vec![0x0102u16];
+        v.to_native(if cfg!(target_endian = "little") {
+            Order::BE
+        } else {
+            Order::LE
+        });
+        assert_eq!(v, vec![0x0201]);
+    }
+
+    #[test]
+    fn no_swap_when_native() {
+        let mut v = vec![0x0102u16];
+        v.to_native(if cfg!(target_endian = "little") {
+            Order::LE
+        } else {
+            Order::BE
+        });
+        assert_eq!(v, vec![0x0102]);
+    }
+}
@@ -0,0 +1,34 @@
+//! The HDF5 deflate (zlib) filter.
+
+use std::io::Read;
+
+use anyhow::Result;
+use flate2::read::ZlibDecoder;
+
+/// Inflate a zlib-compressed chunk. `decompressed_size` is the known
+/// uncompressed size (`chunk_shape.product() * dtype.size()`), used only to
+/// pre-size the output buffer.
+pub fn decompress(input: &[u8], decompressed_size: usize) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(decompressed_size);
+    ZlibDecoder::new(input).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    #[test]
+    fn roundtrip() {
+        let original: Vec<u8> = (0..=255u8).collect();
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&original).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(decompress(&compressed, original.len()).unwrap(), original);
+    }
+}
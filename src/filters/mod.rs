@@ -0,0 +1,147 @@
+//! Reversing the filter pipeline HDF5 applied to a chunk before it was
+//! written to disk.
+//!
+//! A chunk may have gone through several filters on write, in order (e.g.
+//! shuffle, then deflate). [`decode`] walks that pipeline in reverse to get
+//! back the native chunk bytes.
+
+pub mod byteorder;
+pub mod deflate;
+pub mod shuffle;
+
+use anyhow::{bail, Result};
+
+/// Identifies a filter in a dataset's pipeline, in the order HDF5 applied
+/// it on write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterId {
+    Shuffle,
+    Deflate,
+    /// Any other registered HDF5 filter (szip, LZF, zstd, ...), by its
+    /// numeric id, not yet supported by [`decode`].
+    Other(u16),
+}
+
+impl FilterId {
+    pub fn from_hdf5_id(id: u16) -> FilterId {
+        match id {
+            1 => FilterId::Deflate,
+            2 => FilterId::Shuffle,
+            id => FilterId::Other(id),
+        }
+    }
+}
+
+/// Reverses a single filter. `hint` is whatever the codec needs to do its
+/// job without guessing: the target size for a decompressor, the element
+/// size for a byte-rearranging filter like shuffle.
+pub type Decoder = fn(&[u8], usize) -> Result<Vec<u8>>;
+
+fn decoder(id: FilterId) -> Option<Decoder> {
+    match id {
+        FilterId::Deflate => Some(deflate::decompress),
+        FilterId::Shuffle => Some(unshuffle),
+        // szip, LZF, zstd, ... register their decoders here.
+        FilterId::Other(_) => None,
+    }
+}
+
+fn unshuffle(buf: &[u8], elem_sz: usize) -> Result<Vec<u8>> {
+    Ok(shuffle::unshuffle_sized(buf, elem_sz))
+}
+
+/// Run a chunk's filter pipeline in reverse, turning its raw on-disk bytes
+/// into `chunk_shape.product() * dtype.size()` bytes of native chunk data.
+pub fn decode(
+    filters: &[FilterId],
+    buf: Vec<u8>,
+    elem_sz: usize,
+    decompressed_size: usize,
+) -> Result<Vec<u8>> {
+    let mut buf = buf;
+
+    for &id in filters.iter().rev() {
+        let hint = if id == FilterId::Shuffle {
+            elem_sz
+        } else {
+            decompressed_size
+        };
+
+        buf = match decoder(id) {
+            Some(dec) => dec(&buf, hint)?,
+            None => bail!("unsupported filter in pipeline: {:?}", id),
+        };
+    }
+
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_filters_is_a_no_op() {
+        let buf = vec![1, 2, 3, 4];
+        assert_eq!(decode(&[], buf.clone(), 4, 4).unwrap(), buf);
+    }
+
+    #[test]
+    fn decodes_a_deflated_chunk() {
+        use flate2::write::ZlibEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let original: Vec<u8> = (0..64u8).collect();
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&original).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let decoded = decode(&[FilterId::Deflate], compressed, 4, original.len()).unwrap();
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn decodes_a_shuffled_then_deflated_chunk() {
+        use flate2::write::ZlibEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        // 4 little-endian u32 elements: shuffle groups their bytes by
+        // position (all byte-0s, then all byte-1s, ...) before deflate
+        // compresses the result.
+        let elem_sz = 4;
+        let original: Vec<u8> = (0u32..4)
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+
+        let shuffled = {
+            let n = original.len() / elem_sz;
+            let mut out = vec![0u8; original.len()];
+            for byte in 0..elem_sz {
+                for i in 0..n {
+                    out[byte * n + i] = original[i * elem_sz + byte];
+                }
+            }
+            out
+        };
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&shuffled).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        // Filters are recorded in write order (shuffle, then deflate); decode
+        // must walk them in reverse (undo deflate, then undo shuffle) to get
+        // back the original bytes.
+        let decoded = decode(
+            &[FilterId::Shuffle, FilterId::Deflate],
+            compressed,
+            elem_sz,
+            original.len(),
+        )
+        .unwrap();
+
+        assert_eq!(decoded, original);
+    }
+}
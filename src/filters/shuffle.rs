@@ -0,0 +1,45 @@
+//! The HDF5 shuffle filter re-groups the bytes of a chunk by their position
+//! within each element: the first byte of every element comes first, then
+//! the second byte of every element, and so on. Undoing it is a
+//! straightforward transpose.
+
+/// Reconstruct the original element bytes from a shuffled buffer of
+/// elements `elem_sz` bytes wide.
+pub fn unshuffle_sized(buf: &[u8], elem_sz: usize) -> Vec<u8> {
+    if elem_sz <= 1 {
+        return buf.to_vec();
+    }
+
+    let n = buf.len() / elem_sz;
+    let mut out = vec![0u8; buf.len()];
+
+    for byte in 0..elem_sz {
+        for i in 0..n {
+            out[i * elem_sz + byte] = buf[byte * n + i];
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let elem_sz = 4;
+        let n = 3;
+
+        let original: Vec<u8> = (0..(n * elem_sz) as u8).collect();
+
+        let mut shuffled = vec![0u8; original.len()];
+        for byte in 0..elem_sz {
+            for i in 0..n {
+                shuffled[byte * n + i] = original[i * elem_sz + byte];
+            }
+        }
+
+        assert_eq!(unshuffle_sized(&shuffled, elem_sz), original);
+    }
+}
@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::anyhow;
+use hdf5_sys::h5t::H5T_order_t;
+
+use crate::filters::FilterId;
+
+/// Source of the process-wide unique IDs handed out by [`Dataset::new`],
+/// used to key cache entries by dataset identity (see [`Dataset::id`])
+/// rather than by address, which is only unique for as long as a particular
+/// allocation is alive.
+static NEXT_DATASET_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The number of elements per dimension a hyperslab of `counts` elements
+/// per dimension yields once `strides` is taken into account: `ceil(c / s)`
+/// per dimension. `strides` needs one entry per dimension; the result has
+/// as many entries as the shorter of the two slices.
+pub fn output_counts(counts: &[u64], strides: &[u64]) -> Vec<u64> {
+    counts
+        .iter()
+        .zip(strides)
+        .map(|(c, s)| (c + s - 1) / s)
+        .collect()
+}
+
+/// The native datatype of a dataset, with the element size in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Datatype {
+    UInt(u8),
+    Int(u8),
+    Float(u8),
+}
+
+impl Datatype {
+    /// Size of a single element, in bytes.
+    pub fn size(&self) -> u8 {
+        match self {
+            Datatype::UInt(sz) | Datatype::Int(sz) | Datatype::Float(sz) => *sz,
+        }
+    }
+}
+
+/// A single chunk of a dataset: its byte range on disk and its position in
+/// the chunk grid (in elements, one entry per dimension).
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    /// Offset of the chunk's first element, in elements, along each dimension.
+    pub offset: Vec<u64>,
+    pub addr: u64,
+    pub size: u64,
+}
+
+/// Metadata and chunk index for a single HDF5 variable, sufficient to read
+/// its values without the HDF5 library.
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    /// A process-wide unique identity for this dataset, handed out once in
+    /// [`Dataset::new`] and carried through `Clone` — distinct from every
+    /// other `Dataset`, including ones freed and reallocated at the same
+    /// address, so it is safe to use as a cache key even after the `Index`
+    /// that produced this dataset is dropped.
+    pub(crate) id: u64,
+    pub(crate) shape: Vec<u64>,
+    pub(crate) chunk_shape: Vec<u64>,
+    pub(crate) dtype: Datatype,
+    pub(crate) order: H5T_order_t,
+    /// The filters applied to each chunk, in write order. `read` must walk
+    /// this in reverse to recover the native bytes.
+    pub(crate) filters: Vec<FilterId>,
+    pub(crate) chunks: Vec<Chunk>,
+
+    /// Maps a chunk's grid coordinate (`offset[d] / chunk_shape[d]`) to its
+    /// index in `chunks`, so `chunk_slices` can look up the chunk covering a
+    /// given position in constant time.
+    pub(crate) chunk_index: HashMap<Vec<u64>, usize>,
+}
+
+impl Dataset {
+    pub(crate) fn new(
+        shape: Vec<u64>,
+        chunk_shape: Vec<u64>,
+        dtype: Datatype,
+        order: H5T_order_t,
+        filters: Vec<FilterId>,
+        chunks: Vec<Chunk>,
+    ) -> Dataset {
+        let chunk_index = chunks
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let coord = c
+                    .offset
+                    .iter()
+                    .zip(&chunk_shape)
+                    .map(|(o, sz)| o / sz)
+                    .collect::<Vec<_>>();
+                (coord, i)
+            })
+            .collect();
+
+        Dataset {
+            id: NEXT_DATASET_ID.fetch_add(1, Ordering::Relaxed),
+            shape,
+            chunk_shape,
+            dtype,
+            order,
+            filters,
+            chunks,
+            chunk_index,
+        }
+    }
+
+    /// This dataset's process-wide unique identity, stable across clones.
+    /// Used to key a [`crate::reader::SharedCache`] entry.
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn shape(&self) -> &[u64] {
+        &self.shape
+    }
+
+    pub fn chunk_shape(&self) -> &[u64] {
+        &self.chunk_shape
+    }
+
+    pub fn dtype(&self) -> Datatype {
+        self.dtype
+    }
+
+    pub fn order(&self) -> H5T_order_t {
+        self.order
+    }
+
+    pub fn filters(&self) -> &[FilterId] {
+        &self.filters
+    }
+
+    /// Total number of elements in the dataset.
+    pub fn size(&self) -> usize {
+        self.shape.iter().product::<u64>() as usize
+    }
+
+    /// Yields, in the order they must be written into a row-major output
+    /// buffer of shape `output_counts(counts, strides)`, the
+    /// `(chunk, start, step, n)` runs that make up the hyperslab described
+    /// by `indices`/`counts`/`strides` (each defaulting to the whole array
+    /// with a step of 1): `n` elements, `step` chunk-local elements apart,
+    /// starting at the chunk-local element offset `start`. `step` is 1 for
+    /// an unstrided read, in which case the run is a plain contiguous
+    /// `[start, start + n)`.
+    ///
+    /// Errors if the hyperslab touches a chunk that was never allocated
+    /// (HDF5 leaves unwritten chunks of a chunked dataset unallocated, read
+    /// back as the fill value) — both indexers only ever record allocated
+    /// chunks, so there is no chunk to read the data from.
+    pub fn chunk_slices<'a>(
+        &'a self,
+        indices: Option<&[u64]>,
+        counts: Option<&[u64]>,
+        strides: Option<&[u64]>,
+    ) -> Result<impl Iterator<Item = (&'a Chunk, u64, u64, u64)> + 'a, anyhow::Error> {
+        let nd = self.shape.len();
+        let indices: Vec<u64> = indices.map(<[u64]>::to_vec).unwrap_or_else(|| vec![0; nd]);
+        let counts: Vec<u64> = counts
+            .map(<[u64]>::to_vec)
+            .unwrap_or_else(|| self.shape.clone());
+        let strides: Vec<u64> = strides.map(<[u64]>::to_vec).unwrap_or_else(|| vec![1; nd]);
+
+        let mut runs = Vec::new();
+
+        if nd == 0 || counts.iter().any(|&c| c == 0) {
+            return Ok(runs.into_iter());
+        }
+
+        let out_counts = output_counts(&counts, &strides);
+
+        let last = nd - 1;
+        let mut outer = vec![0u64; last]; // output index along each outer dim
+
+        loop {
+            let step = strides[last];
+            let n_out = out_counts[last];
+            let mut oi = 0u64; // output index along the last dim, within this row
+
+            while oi < n_out {
+                let i = indices[last] + oi * step;
+
+                let chunk_coord: Vec<u64> = (0..last)
+                    .map(|d| (indices[d] + outer[d] * strides[d]) / self.chunk_shape[d])
+                    .chain(std::iter::once(i / self.chunk_shape[last]))
+                    .collect();
+
+                let chunk_idx = self.chunk_index.get(&chunk_coord).ok_or_else(|| {
+                    anyhow!(
+                        "chunk {:?} is unallocated (fill-value read is not supported)",
+                        chunk_coord
+                    )
+                })?;
+                let chunk = &self.chunks[*chunk_idx];
+
+                // how many more strided elements fit before running past this
+                // chunk, the requested span, or the output count
+                let chunk_end = (chunk_coord[last] + 1) * self.chunk_shape[last];
+                let span_end = indices[last] + counts[last];
+                let end = chunk_end.min(span_end);
+                let n = (end - i + step - 1) / step;
+                let n = n.min(n_out - oi);
+
+                let mut local_start = 0u64;
+                for d in 0..nd {
+                    let global = if d == last {
+                        i
+                    } else {
+                        indices[d] + outer[d] * strides[d]
+                    };
+                    let local = global - chunk_coord[d] * self.chunk_shape[d];
+                    local_start = local_start * self.chunk_shape[d] + local;
+                }
+
+                runs.push((chunk, local_start, step, n));
+                oi += n;
+            }
+
+            if last == 0 {
+                break;
+            }
+
+            let mut d = last - 1;
+            loop {
+                outer[d] += 1;
+                if outer[d] < out_counts[d] {
+                    break;
+                }
+                outer[d] = 0;
+                if d == 0 {
+                    return Ok(runs.into_iter());
+                }
+                d -= 1;
+            }
+        }
+
+        Ok(runs.into_iter())
+    }
+
+    pub fn as_reader<'a>(
+        &'a self,
+        path: &Path,
+    ) -> Result<crate::reader::DatasetReader<'a>, anyhow::Error> {
+        crate::reader::DatasetReader::with_dataset(self, path)
+    }
+
+    pub fn as_par_reader<'a>(
+        &'a self,
+        path: &Path,
+    ) -> Result<crate::reader::ParDatasetReader<'a>, anyhow::Error> {
+        crate::reader::ParDatasetReader::with_dataset(self, path)
+    }
+
+    /// Like [`Dataset::as_reader`], but consulting and populating `cache`
+    /// instead of a private one. Pass the same [`crate::reader::SharedCache`]
+    /// to readers built from several datasets of one [`crate::idx::Index`]
+    /// (or to both this and [`Dataset::as_par_reader_with_cache`]) to have
+    /// them share decompressed chunks.
+    pub fn as_reader_with_cache<'a>(
+        &'a self,
+        path: &Path,
+        cache: crate::reader::SharedCache,
+    ) -> Result<crate::reader::DatasetReader<'a>, anyhow::Error> {
+        crate::reader::DatasetReader::with_cache(self, path, cache)
+    }
+
+    /// Like [`Dataset::as_par_reader`], but consulting and populating
+    /// `cache` instead of a private one.
+    pub fn as_par_reader_with_cache<'a>(
+        &'a self,
+        path: &Path,
+        cache: crate::reader::SharedCache,
+    ) -> Result<crate::reader::ParDatasetReader<'a>, anyhow::Error> {
+        crate::reader::ParDatasetReader::with_cache(self, path, cache)
+    }
+}
+
+/// A dataset tied to the lifetime of the [`crate::idx::Index`] that owns it.
+///
+/// Indices built from borrowed data (e.g. memory-mapped sidecars) can carry
+/// that borrow through to the reader; indices built from owned data (the
+/// common case) simply use `'static`.
+#[derive(Debug, Clone)]
+pub struct DatasetD<'a> {
+    dataset: Dataset,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> DatasetD<'a> {
+    pub(crate) fn new(dataset: Dataset) -> DatasetD<'a> {
+        DatasetD {
+            dataset,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a> std::ops::Deref for DatasetD<'a> {
+    type Target = Dataset;
+
+    fn deref(&self) -> &Dataset {
+        &self.dataset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hdf5_sys::h5t::H5T_order_t::H5T_ORDER_LE;
+
+    #[test]
+    fn chunk_slices_errors_on_unallocated_chunk() {
+        // A 20x20 dataset in 10x10 chunks, but none of its 4 chunks were
+        // ever written (e.g. a dataset created but never filled in).
+        let ds = Dataset::new(
+            vec![20, 20],
+            vec![10, 10],
+            Datatype::Float(4),
+            H5T_ORDER_LE,
+            Vec::new(),
+            Vec::new(),
+        );
+
+        assert!(ds.chunk_slices(None, None, None).is_err());
+    }
+
+    #[test]
+    fn chunk_slices_with_stride_crosses_chunk_boundary() {
+        // Two 10-element chunks covering a 20-element 1D dataset. Reading
+        // 10 elements with a stride of 2 starting at index 5 (`ds[5:15:2]`)
+        // crosses the chunk boundary at 10 partway through the run, at an
+        // offset (5) that isn't itself a multiple of the stride.
+        let chunks = vec![
+            Chunk {
+                offset: vec![0],
+                addr: 100,
+                size: 40,
+            },
+            Chunk {
+                offset: vec![10],
+                addr: 140,
+                size: 40,
+            },
+        ];
+        let ds = Dataset::new(
+            vec![20],
+            vec![10],
+            Datatype::Float(4),
+            H5T_ORDER_LE,
+            Vec::new(),
+            chunks,
+        );
+
+        let runs: Vec<(u64, u64, u64, u64)> = ds
+            .chunk_slices(Some(&[5]), Some(&[10]), Some(&[2]))
+            .unwrap()
+            .map(|(c, start, step, n)| (c.addr, start, step, n))
+            .collect();
+
+        // 3 elements (local offsets 5, 7, 9) out of the first chunk, then 2
+        // (local offsets 1, 3) out of the second, for the 5 elements
+        // `ceil(10 / 2)` strided reads covers.
+        assert_eq!(runs, vec![(100, 5, 2, 3), (140, 1, 2, 2)]);
+    }
+
+    #[test]
+    fn id_is_distinct_per_dataset_and_stable_across_clones() {
+        let a = Dataset::new(
+            vec![1],
+            vec![1],
+            Datatype::Float(4),
+            H5T_ORDER_LE,
+            Vec::new(),
+            Vec::new(),
+        );
+        let b = Dataset::new(
+            vec![1],
+            vec![1],
+            Datatype::Float(4),
+            H5T_ORDER_LE,
+            Vec::new(),
+            Vec::new(),
+        );
+
+        assert_ne!(a.id(), b.id());
+        assert_eq!(a.id(), a.clone().id());
+    }
+}
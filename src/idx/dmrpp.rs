@@ -0,0 +1,338 @@
+//! Building an [`Index`] from a DMR++ XML sidecar (as produced by
+//! `build_dmrpp`), without ever opening the HDF5 file it describes.
+//!
+//! DMR++ is a `DMR` (a DAP4 dataset description) with an extra `dmrpp`
+//! namespace describing where each variable's chunks live in the underlying
+//! file:
+//!
+//! ```xml
+//! <Float32 name="SST">
+//!   <Dim name="/COADSX"/>
+//!   <dmrpp:chunks compressionType="deflate" dmrpp:byteOrder="LE">
+//!     <dmrpp:chunkDimensionSizes>1 180 360</dmrpp:chunkDimensionSizes>
+//!     <dmrpp:chunk offset="40000" nBytes="972" chunkPositionInArray="[0,0,0]"/>
+//!   </dmrpp:chunks>
+//! </Float32>
+//! ```
+//!
+//! Contiguous (unchunked) variables instead carry a single `dmrpp:chunk`
+//! directly, with no `dmrpp:chunks` wrapper and no `chunkPositionInArray`.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use hdf5_sys::h5t::H5T_order_t;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use crate::filters::FilterId;
+
+use super::dataset::{Chunk, Dataset, Datatype};
+use super::{DatasetD, Index};
+
+pub(crate) fn from_dmrpp(path: &Path) -> Result<Index<'static>, anyhow::Error> {
+    let f = File::open(path).with_context(|| format!("opening {:?}", path))?;
+    let mut reader = Reader::from_reader(BufReader::new(f));
+    reader.trim_text(true);
+
+    let mut buf = Vec::new();
+
+    let mut dimensions: HashMap<String, u64> = HashMap::new();
+    let mut datasets: HashMap<String, DatasetD<'static>> = HashMap::new();
+
+    let mut current: Option<Var> = None;
+    let mut data_href: Option<String> = None;
+
+    loop {
+        match reader
+            .read_event(&mut buf)
+            .with_context(|| format!("parsing {:?}", path))?
+        {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let local = local_name(e.name());
+
+                if local == "Dataset" && data_href.is_none() {
+                    let attrs = attributes(e)?;
+                    data_href = attrs.get("href").or_else(|| attrs.get("name")).cloned();
+                } else if local == "Dimension" {
+                    let attrs = attributes(e)?;
+                    if let (Some(name), Some(size)) = (attrs.get("name"), attrs.get("size")) {
+                        dimensions.insert(name.trim_start_matches('/').to_string(), size.parse()?);
+                    }
+                } else if let Some(dtype) = dap_datatype(local) {
+                    let attrs = attributes(e)?;
+                    let name = attrs
+                        .get("name")
+                        .ok_or_else(|| anyhow!("variable without a name"))?
+                        .clone();
+                    let order = byte_order(attrs.get("byteOrder").map(String::as_str));
+
+                    current = Some(Var {
+                        name,
+                        dtype,
+                        order,
+                        dims: Vec::new(),
+                        chunk_shape: Vec::new(),
+                        filters: Vec::new(),
+                        chunks: Vec::new(),
+                    });
+                } else if local == "Dim" {
+                    if let Some(var) = current.as_mut() {
+                        let attrs = attributes(e)?;
+                        let size = if let Some(sz) = attrs.get("size") {
+                            sz.parse()?
+                        } else if let Some(name) = attrs.get("name") {
+                            *dimensions
+                                .get(name.trim_start_matches('/'))
+                                .ok_or_else(|| anyhow!("unknown dimension {}", name))?
+                        } else {
+                            return Err(anyhow!("<Dim> without name or size"));
+                        };
+                        var.dims.push(size);
+                    }
+                } else if local == "chunks" {
+                    if let Some(var) = current.as_mut() {
+                        let attrs = attributes(e)?;
+                        if let Some(compression) = attrs.get("compressionType") {
+                            var.filters = parse_compression(compression);
+                        }
+                        if let Some(order) = attrs.get("byteOrder") {
+                            var.order = byte_order(Some(order));
+                        }
+                    }
+                } else if local == "chunkDimensionSizes" {
+                    if let Some(var) = current.as_mut() {
+                        let text = reader.read_text(e.name(), &mut Vec::new())?;
+                        var.chunk_shape = text
+                            .split_whitespace()
+                            .map(str::parse)
+                            .collect::<Result<_, _>>()?;
+                    }
+                } else if local == "chunk" {
+                    if let Some(var) = current.as_mut() {
+                        let attrs = attributes(e)?;
+                        let addr: u64 = attrs
+                            .get("offset")
+                            .ok_or_else(|| anyhow!("dmrpp:chunk without offset"))?
+                            .parse()?;
+                        let size: u64 = attrs
+                            .get("nBytes")
+                            .ok_or_else(|| anyhow!("dmrpp:chunk without nBytes"))?
+                            .parse()?;
+                        let offset = match attrs.get("chunkPositionInArray") {
+                            Some(p) => parse_position(p)?,
+                            None => vec![0; var.dims.len().max(1)],
+                        };
+
+                        var.chunks.push(Chunk { offset, addr, size });
+                    }
+                }
+            }
+
+            Event::End(ref e) => {
+                let local = local_name(e.name());
+
+                if dap_datatype(local).is_some() {
+                    if let Some(var) = current.take() {
+                        let (name, dataset) = var.finish();
+                        datasets.insert(name, DatasetD::new(dataset));
+                    }
+                }
+            }
+
+            Event::Eof => break,
+            _ => {}
+        }
+
+        buf.clear();
+    }
+
+    Ok(Index::new(
+        Some(data_file_path(path, data_href.as_deref())?),
+        datasets,
+    ))
+}
+
+/// The data file a `.dmrpp` sidecar at `sidecar` describes: the root
+/// `<Dataset>` element's `dmrpp:href` (or, failing that, its `name`),
+/// resolved relative to the sidecar's directory, since the two are expected
+/// to sit side by side. Falls back to the sidecar's own name with the
+/// `.dmrpp` suffix stripped if the DMR++ carries neither attribute.
+///
+/// Errors if the href points at a remote object (e.g. the `https://` URLs
+/// Hyrax-generated DMR++ carries by default): readers here only ever read
+/// through `std::fs`, so there is no local path to resolve to, and silently
+/// joining the URL onto the sidecar's directory would build a bogus path
+/// instead of failing loudly.
+fn data_file_path(sidecar: &Path, href: Option<&str>) -> Result<PathBuf, anyhow::Error> {
+    let name = href.map(str::to_string).unwrap_or_else(|| {
+        sidecar
+            .file_name()
+            .and_then(|f| f.to_str())
+            .map(|f| f.trim_end_matches(".dmrpp").to_string())
+            .unwrap_or_default()
+    });
+
+    if let Some((scheme, _)) = name.split_once("://") {
+        if !scheme.eq_ignore_ascii_case("file") {
+            return Err(anyhow!(
+                "DMR++ data href {:?} is remote ({}://); reading remote data files is not supported yet",
+                name,
+                scheme
+            ));
+        }
+    }
+
+    let name = name
+        .trim_start_matches("file://")
+        .trim_start_matches("file:");
+    let name = Path::new(name);
+
+    Ok(if name.is_absolute() {
+        name.to_path_buf()
+    } else {
+        sidecar.parent().unwrap_or_else(|| Path::new("")).join(name)
+    })
+}
+
+struct Var {
+    name: String,
+    dtype: Datatype,
+    order: H5T_order_t,
+    dims: Vec<u64>,
+    chunk_shape: Vec<u64>,
+    filters: Vec<FilterId>,
+    chunks: Vec<Chunk>,
+}
+
+impl Var {
+    fn finish(self) -> (String, Dataset) {
+        let shape = self.dims;
+
+        let chunk_shape = if self.chunk_shape.is_empty() {
+            // No `dmrpp:chunkDimensionSizes`: either a scalar or a
+            // contiguous variable with a single implicit chunk spanning the
+            // whole array.
+            shape.clone()
+        } else {
+            self.chunk_shape
+        };
+
+        (
+            self.name,
+            Dataset::new(
+                shape,
+                chunk_shape,
+                self.dtype,
+                self.order,
+                self.filters,
+                self.chunks,
+            ),
+        )
+    }
+}
+
+fn local_name(name: &[u8]) -> &str {
+    let name = std::str::from_utf8(name).unwrap_or("");
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn attributes(e: &quick_xml::events::BytesStart) -> Result<HashMap<String, String>, anyhow::Error> {
+    e.attributes()
+        .map(|a| {
+            let a = a?;
+            let value = String::from_utf8(a.value.into_owned())?;
+            Ok((local_name(a.key).to_string(), value))
+        })
+        .collect()
+}
+
+/// `chunkPositionInArray="[0,180,0]"` -> `[0, 180, 0]`
+fn parse_position(s: &str) -> Result<Vec<u64>, anyhow::Error> {
+    s.trim_matches(|c| c == '[' || c == ']')
+        .split(',')
+        .map(|p| p.trim().parse().map_err(anyhow::Error::from))
+        .collect()
+}
+
+/// `compressionType="shuffle deflate"` -> the filters applied, in that
+/// (write) order.
+fn parse_compression(s: &str) -> Vec<FilterId> {
+    s.split_whitespace()
+        .filter_map(|c| match c {
+            "shuffle" => Some(FilterId::Shuffle),
+            "deflate" => Some(FilterId::Deflate),
+            _ => None,
+        })
+        .collect()
+}
+
+fn dap_datatype(name: &str) -> Option<Datatype> {
+    Some(match name {
+        "Byte" | "UInt8" => Datatype::UInt(1),
+        "Int8" => Datatype::Int(1),
+        "UInt16" => Datatype::UInt(2),
+        "Int16" => Datatype::Int(2),
+        "UInt32" => Datatype::UInt(4),
+        "Int32" => Datatype::Int(4),
+        "UInt64" => Datatype::UInt(8),
+        "Int64" => Datatype::Int(8),
+        "Float32" => Datatype::Float(4),
+        "Float64" => Datatype::Float(8),
+        _ => return None,
+    })
+}
+
+fn byte_order(s: Option<&str>) -> H5T_order_t {
+    match s {
+        Some("BE") => H5T_order_t::H5T_ORDER_BE,
+        _ => H5T_order_t::H5T_ORDER_LE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_chunked() {
+        let i = from_dmrpp(Path::new("tests/data/dmrpp/chunked_twoD.h5.dmrpp")).unwrap();
+        let d = i.dataset("d_4_chunks").unwrap();
+
+        assert_eq!(d.shape(), &[20, 20]);
+        assert_eq!(d.chunk_shape(), &[10, 10]);
+    }
+
+    #[test]
+    fn index_coads_contiguous() {
+        let i = from_dmrpp(Path::new("tests/data/dmrpp/coads_climatology.nc4.dmrpp")).unwrap();
+        let d = i.dataset("SST").unwrap();
+
+        assert_eq!(d.shape(), &[12, 90, 180]);
+    }
+
+    #[test]
+    fn data_file_path_errors_on_remote_href() {
+        let err = data_file_path(
+            Path::new("/sidecars/coads.nc4.dmrpp"),
+            Some("https://example.org/data/coads.nc4"),
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("remote"));
+    }
+
+    #[test]
+    fn data_file_path_resolves_local_href() {
+        let p = data_file_path(
+            Path::new("/sidecars/coads.nc4.dmrpp"),
+            Some("file:coads.nc4"),
+        )
+        .unwrap();
+
+        assert_eq!(p, Path::new("/sidecars/coads.nc4"));
+    }
+}
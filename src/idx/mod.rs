@@ -0,0 +1,54 @@
+//! Indexing of HDF5 files: mapping each dataset to the byte ranges of its
+//! chunks on disk, either by opening the file itself or by reading a DMR++
+//! XML sidecar describing it.
+
+mod dataset;
+mod dmrpp;
+mod native;
+
+pub use dataset::{output_counts, Chunk, Dataset, DatasetD, Datatype};
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// An index of the datasets in a single HDF5 file.
+#[derive(Debug)]
+pub struct Index<'a> {
+    path: Option<PathBuf>,
+    datasets: HashMap<String, DatasetD<'a>>,
+}
+
+impl<'a> Index<'a> {
+    pub(crate) fn new(path: Option<PathBuf>, datasets: HashMap<String, DatasetD<'a>>) -> Index<'a> {
+        Index { path, datasets }
+    }
+
+    /// Index an HDF5 file by opening it and walking its datasets.
+    pub fn index<P>(path: P) -> Result<Index<'static>, anyhow::Error>
+    where
+        P: AsRef<Path>,
+    {
+        native::index(path.as_ref())
+    }
+
+    /// Build an index from a DMR++ XML sidecar, without ever opening the
+    /// HDF5 file it describes.
+    pub fn from_dmrpp<P>(path: P) -> Result<Index<'static>, anyhow::Error>
+    where
+        P: AsRef<Path>,
+    {
+        dmrpp::from_dmrpp(path.as_ref())
+    }
+
+    pub fn dataset(&self, s: &str) -> Option<&DatasetD<'a>> {
+        self.datasets.get(s)
+    }
+
+    pub fn datasets(&self) -> &HashMap<String, DatasetD<'a>> {
+        &self.datasets
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+}
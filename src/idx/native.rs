@@ -0,0 +1,179 @@
+//! Indexing by opening the HDF5 file and asking the library directly for
+//! each dataset's layout and chunk addresses.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use hdf5::{Dataset as H5Dataset, Group};
+use hdf5_sys::h5d::{H5Dget_chunk_info, H5Dget_create_plist, H5Dget_num_chunks, H5Dget_offset};
+use hdf5_sys::h5p::{H5Pclose, H5Pget_filter2, H5Pget_nfilters};
+
+use crate::filters::FilterId;
+
+use super::dataset::{Chunk, Dataset, Datatype};
+use super::{DatasetD, Index};
+
+pub(crate) fn index(path: &Path) -> Result<Index<'static>, anyhow::Error> {
+    let file = hdf5::File::open(path).with_context(|| format!("opening {:?}", path))?;
+
+    let mut datasets = HashMap::new();
+    visit_group(&file, &mut datasets)?;
+
+    Ok(Index::new(Some(PathBuf::from(path)), datasets))
+}
+
+fn visit_group(
+    group: &Group,
+    datasets: &mut HashMap<String, DatasetD<'static>>,
+) -> Result<(), anyhow::Error> {
+    for name in group.member_names()? {
+        if let Ok(ds) = group.dataset(&name) {
+            let indexed = index_dataset(&ds)?;
+            datasets.insert(name, DatasetD::new(indexed));
+        } else if let Ok(sub) = group.group(&name) {
+            visit_group(&sub, datasets)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn index_dataset(ds: &H5Dataset) -> Result<Dataset, anyhow::Error> {
+    let shape: Vec<u64> = ds.shape().into_iter().map(|d| d as u64).collect();
+    let dtype = datatype(ds)?;
+    let order = ds
+        .dtype()?
+        .byte_order()
+        .unwrap_or(hdf5_sys::h5t::H5T_order_t::H5T_ORDER_LE);
+
+    let layout = ds.layout();
+    let filters = dataset_filters(ds)?;
+
+    let (chunk_shape, chunks) = if let Some(chunk_shape) = layout.chunk() {
+        let chunk_shape: Vec<u64> = chunk_shape.into_iter().map(|d| d as u64).collect();
+        (chunk_shape, chunk_addresses(ds, shape.len())?)
+    } else {
+        // Contiguous dataset: a single implicit chunk covering the whole array.
+        let addr = unsafe { H5Dget_offset(ds.id()) };
+        let size = shape.iter().product::<u64>() * dtype.size() as u64;
+
+        (
+            shape.clone(),
+            vec![Chunk {
+                offset: vec![0; shape.len()],
+                addr,
+                size,
+            }],
+        )
+    };
+
+    Ok(Dataset::new(
+        shape,
+        chunk_shape,
+        dtype,
+        order,
+        filters,
+        chunks,
+    ))
+}
+
+/// Enumerate the on-disk address and size of every allocated chunk of a
+/// chunked dataset, using the low-level `H5Dget_chunk_info` API (HDF5 >=
+/// 1.10.5).
+fn chunk_addresses(ds: &H5Dataset, ndim: usize) -> Result<Vec<Chunk>, anyhow::Error> {
+    let mut n: hdf5_sys::h5::hsize_t = 0;
+    unsafe {
+        H5Dget_num_chunks(ds.id(), -1, &mut n);
+    }
+
+    let mut chunks = Vec::with_capacity(n as usize);
+
+    for i in 0..n {
+        let mut offset = vec![0 as hdf5_sys::h5::hsize_t; ndim];
+        let mut filter_mask: u32 = 0;
+        let mut addr: hdf5_sys::h5::haddr_t = 0;
+        let mut size: hdf5_sys::h5::hsize_t = 0;
+
+        let err = unsafe {
+            H5Dget_chunk_info(
+                ds.id(),
+                -1,
+                i,
+                offset.as_mut_ptr(),
+                &mut filter_mask,
+                &mut addr,
+                &mut size,
+            )
+        };
+
+        if err < 0 {
+            return Err(anyhow!("failed to read chunk info for chunk {}", i));
+        }
+
+        chunks.push(Chunk {
+            offset: offset.into_iter().map(|o| o as u64).collect(),
+            addr: addr as u64,
+            size: size as u64,
+        });
+    }
+
+    Ok(chunks)
+}
+
+/// The filters applied to a dataset's chunks, in write/pipeline order, read
+/// off its creation property list via the low-level `H5Pget_filter2` API —
+/// the high-level `hdf5` crate's `Dataset::filters()` only decodes a fixed
+/// set of well-known filters into named fields, not the raw pipeline, so it
+/// can't tell us the write order `filters::decode` needs to walk in reverse.
+fn dataset_filters(ds: &H5Dataset) -> Result<Vec<FilterId>, anyhow::Error> {
+    unsafe {
+        let plist = H5Dget_create_plist(ds.id());
+        if plist < 0 {
+            return Err(anyhow!("failed to get creation property list for dataset"));
+        }
+
+        let nfilters = H5Pget_nfilters(plist);
+        if nfilters < 0 {
+            H5Pclose(plist);
+            return Err(anyhow!("failed to get filter count for dataset"));
+        }
+
+        let mut filters = Vec::with_capacity(nfilters as usize);
+        for i in 0..nfilters as u32 {
+            let id = H5Pget_filter2(
+                plist,
+                i,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                0,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            );
+
+            if id < 0 {
+                H5Pclose(plist);
+                return Err(anyhow!("failed to read filter {} for dataset", i));
+            }
+
+            filters.push(FilterId::from_hdf5_id(id as u16));
+        }
+
+        H5Pclose(plist);
+        Ok(filters)
+    }
+}
+
+fn datatype(ds: &H5Dataset) -> Result<Datatype, anyhow::Error> {
+    use hdf5::types::TypeDescriptor::*;
+
+    let sz = ds.dtype()?.size() as u8;
+
+    Ok(match ds.dtype()?.to_descriptor()? {
+        Integer(_) => Datatype::Int(sz),
+        Unsigned(_) => Datatype::UInt(sz),
+        Float(_) => Datatype::Float(sz),
+        dt => return Err(anyhow!("unsupported datatype: {:?}", dt)),
+    })
+}
@@ -0,0 +1,14 @@
+//! hidefix: a concurrency-oriented indexer and reader for HDF5 files.
+//!
+//! The crate is split into an indexer (`idx`) which scans an HDF5 file (or a
+//! lightweight DMR++ sidecar describing one) for the byte ranges of its
+//! chunks, and a reader (`reader`) which uses that index to pull values out
+//! without going through the HDF5 library itself.
+
+pub mod filters;
+pub mod idx;
+pub mod prelude;
+pub mod reader;
+
+#[cfg(feature = "python")]
+pub mod python;
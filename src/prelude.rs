@@ -0,0 +1,3 @@
+//! Commonly used types re-exported for convenience.
+
+pub use crate::idx::Datatype;
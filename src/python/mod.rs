@@ -68,16 +68,19 @@ impl Dataset {
         ds: &idx::DatasetD<'_>,
         indices: &[u64],
         counts: &[u64],
+        strides: &[u64],
     ) -> PyResult<&'py PyAny>
     where
         T: numpy::Element + ToMutByteSlice + 'py,
         [T]: ToNative,
     {
         let r = ds.as_par_reader(self.idx.path().unwrap())?;
+        let out_counts = idx::output_counts(counts, strides);
+
         let (a, dst) = unsafe {
             let a = PyArray::<T, _>::new(
                 py,
-                counts
+                out_counts
                     .iter()
                     .cloned()
                     .map(|d| d as usize)
@@ -89,9 +92,47 @@ impl Dataset {
             (a, dst)
         };
 
-        r.values_to_par(Some(indices), Some(counts), dst)?;
+        r.values_to_par(Some(indices), Some(counts), Some(strides), dst)?;
         Ok(a.as_ref())
     }
+
+    /// Like [`Dataset::read_py_array`], but hands back a `pyarrow.Array`
+    /// sharing hidefix's decoded buffer (via the Arrow C Data Interface)
+    /// instead of copying into a fresh numpy array, so the dataset can be
+    /// used as a DataFrame column without a further copy at the boundary.
+    #[cfg(feature = "arrow")]
+    fn read_arrow_column<'py, T>(
+        &self,
+        py: Python<'py>,
+        ds: &idx::DatasetD<'_>,
+        indices: &[u64],
+        counts: &[u64],
+        strides: &[u64],
+    ) -> PyResult<&'py PyAny>
+    where
+        T: arrow2::types::NativeType + byte_slice_cast::FromByteVec,
+        [T]: ToNative,
+    {
+        let mut r = ds.as_reader(self.idx.path().unwrap())?;
+        let array = r.values_arrow::<T>(Some(indices), Some(counts), Some(strides))?;
+        let field = arrow2::datatypes::Field::new("", array.data_type().clone(), true);
+
+        let array: Box<dyn arrow2::array::Array> = Box::new(array);
+        let (ffi_array, ffi_schema) = unsafe {
+            (
+                arrow2::ffi::export_array_to_c(array),
+                arrow2::ffi::export_field_to_c(&field),
+            )
+        };
+
+        py.import("pyarrow")?.getattr("Array")?.call_method1(
+            "_import_from_c",
+            (
+                &ffi_array as *const _ as usize,
+                &ffi_schema as *const _ as usize,
+            ),
+        )
+    }
 }
 
 #[pymethods]
@@ -116,9 +157,6 @@ impl Dataset {
         let ds = self.idx.dataset(&self.ds).unwrap();
         let shape = ds.shape();
 
-        println!("dtype: {:?}", ds.dtype());
-        println!("shape: {:?}", shape);
-
         // if there are fewer slices than dimensions they will be extended by the full dimension
         // when read.
         let (mut indices, (mut counts, mut strides)): (Vec<_>, (Vec<_>, Vec<_>)) = slice
@@ -140,41 +178,81 @@ impl Dataset {
         strides.resize_with(shape.len(), || 1);
         counts.extend_from_slice(&shape[counts.len()..]);
 
-        dbg!(&indices);
-        dbg!(&counts);
-        dbg!(&strides);
-
         // read the data into correct datatype, convert to pyarray and cast as pyany.
         match ds.dtype() {
             Datatype::UInt(sz) if sz == 1 => {
-                self.read_py_array::<u8>(py, ds, &indices, &counts)
+                self.read_py_array::<u8>(py, ds, &indices, &counts, &strides)
             }
             Datatype::UInt(sz) if sz == 2 => {
-                self.read_py_array::<u16>(py, ds, &indices, &counts)
+                self.read_py_array::<u16>(py, ds, &indices, &counts, &strides)
             }
             Datatype::UInt(sz) if sz == 4 => {
-                self.read_py_array::<u32>(py, ds, &indices, &counts)
+                self.read_py_array::<u32>(py, ds, &indices, &counts, &strides)
             }
             Datatype::UInt(sz) if sz == 8 => {
-                self.read_py_array::<u64>(py, ds, &indices, &counts)
+                self.read_py_array::<u64>(py, ds, &indices, &counts, &strides)
             }
             Datatype::Int(sz) if sz == 1 => {
-                self.read_py_array::<i8>(py, ds, &indices, &counts)
+                self.read_py_array::<i8>(py, ds, &indices, &counts, &strides)
             }
             Datatype::Int(sz) if sz == 2 => {
-                self.read_py_array::<i16>(py, ds, &indices, &counts)
+                self.read_py_array::<i16>(py, ds, &indices, &counts, &strides)
             }
             Datatype::Int(sz) if sz == 4 => {
-                self.read_py_array::<i32>(py, ds, &indices, &counts)
+                self.read_py_array::<i32>(py, ds, &indices, &counts, &strides)
             }
             Datatype::Int(sz) if sz == 8 => {
-                self.read_py_array::<i64>(py, ds, &indices, &counts)
+                self.read_py_array::<i64>(py, ds, &indices, &counts, &strides)
             }
             Datatype::Float(sz) if sz == 4 => {
-                self.read_py_array::<f32>(py, ds, &indices, &counts)
+                self.read_py_array::<f32>(py, ds, &indices, &counts, &strides)
             }
             Datatype::Float(sz) if sz == 8 => {
-                self.read_py_array::<f64>(py, ds, &indices, &counts)
+                self.read_py_array::<f64>(py, ds, &indices, &counts, &strides)
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    /// The whole dataset as a `pyarrow.Array`, sharing hidefix's decoded
+    /// buffer rather than copying into a numpy array first.
+    #[cfg(feature = "arrow")]
+    fn column<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        let ds = self.idx.dataset(&self.ds).unwrap();
+        let shape = ds.shape().to_vec();
+        let indices = vec![0u64; shape.len()];
+        let strides = vec![1u64; shape.len()];
+
+        match ds.dtype() {
+            Datatype::UInt(sz) if sz == 1 => {
+                self.read_arrow_column::<u8>(py, ds, &indices, &shape, &strides)
+            }
+            Datatype::UInt(sz) if sz == 2 => {
+                self.read_arrow_column::<u16>(py, ds, &indices, &shape, &strides)
+            }
+            Datatype::UInt(sz) if sz == 4 => {
+                self.read_arrow_column::<u32>(py, ds, &indices, &shape, &strides)
+            }
+            Datatype::UInt(sz) if sz == 8 => {
+                self.read_arrow_column::<u64>(py, ds, &indices, &shape, &strides)
+            }
+            Datatype::Int(sz) if sz == 1 => {
+                self.read_arrow_column::<i8>(py, ds, &indices, &shape, &strides)
+            }
+            Datatype::Int(sz) if sz == 2 => {
+                self.read_arrow_column::<i16>(py, ds, &indices, &shape, &strides)
+            }
+            Datatype::Int(sz) if sz == 4 => {
+                self.read_arrow_column::<i32>(py, ds, &indices, &shape, &strides)
+            }
+            Datatype::Int(sz) if sz == 8 => {
+                self.read_arrow_column::<i64>(py, ds, &indices, &shape, &strides)
+            }
+            Datatype::Float(sz) if sz == 4 => {
+                self.read_arrow_column::<f32>(py, ds, &indices, &shape, &strides)
+            }
+            Datatype::Float(sz) if sz == 8 => {
+                self.read_arrow_column::<f64>(py, ds, &indices, &shape, &strides)
             }
             _ => unimplemented!(),
         }
@@ -195,4 +273,16 @@ mod tests {
             println!("{:?}", arr);
         });
     }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn column_coads() {
+        Python::with_gil(|py| {
+            let i = Index::new("tests/data/coads_climatology.nc4".into()).unwrap();
+            let ds = i.dataset("SST").unwrap();
+
+            let col = ds.column(py).unwrap();
+            assert_eq!(col.len().unwrap(), ds.__len__());
+        });
+    }
 }
@@ -2,79 +2,93 @@ use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 
-use lru::LruCache;
 use byte_slice_cast::{FromByteVec, IntoVecOf};
 
 use crate::filters;
 use crate::filters::byteorder::{Order, ToNative};
 use crate::idx::Dataset;
+use crate::reader::copy_run;
+use crate::reader::{SharedCache, DEFAULT_CACHE_BYTES};
 
 pub struct DatasetReader<'a> {
     ds: &'a Dataset,
     fd: File,
-    cache: LruCache<u64, Vec<u8>>,
+    cache: SharedCache,
 }
 
 impl<'a> DatasetReader<'a> {
-    pub fn with_dataset<P>(ds: &'a Dataset, p: P) -> Result<DatasetReader, anyhow::Error>
+    pub fn with_dataset<P>(ds: &'a Dataset, p: P) -> Result<DatasetReader<'a>, anyhow::Error>
     where
         P: AsRef<Path>,
     {
-        let fd = File::open(p)?;
+        DatasetReader::with_cache(ds, p, SharedCache::new(DEFAULT_CACHE_BYTES))
+    }
 
-        const CACHE_SZ: u64 = 32 * 1024 * 1024;
-        let cache_sz = CACHE_SZ / (ds.chunk_shape.iter().product::<u64>() * ds.dtype.size() as u64);
-        println!("cache_sz: {}", cache_sz);
+    /// Like [`DatasetReader::with_dataset`], but consulting and populating
+    /// `cache` instead of a private one — pass the same [`SharedCache`] to
+    /// several readers (including [`crate::reader::ParDatasetReader`]) so
+    /// that a chunk one of them has decompressed is served to the others
+    /// from the cache while it stays cached. Readers that miss the same
+    /// chunk at the same time each decompress it.
+    pub fn with_cache<P>(
+        ds: &'a Dataset,
+        p: P,
+        cache: SharedCache,
+    ) -> Result<DatasetReader<'a>, anyhow::Error>
+    where
+        P: AsRef<Path>,
+    {
+        let fd = File::open(p)?;
 
-        Ok(DatasetReader {
-            ds,
-            fd,
-            cache: LruCache::new(cache_sz as usize),
-        })
+        Ok(DatasetReader { ds, fd, cache })
     }
 
     pub fn read(
         &mut self,
         indices: Option<&[u64]>,
         counts: Option<&[u64]>,
+        strides: Option<&[u64]>,
     ) -> Result<Vec<u8>, anyhow::Error> {
         let counts: &[u64] = counts.unwrap_or_else(|| self.ds.shape.as_slice());
+        let out_counts = match strides {
+            Some(strides) => crate::idx::output_counts(counts, strides),
+            None => counts.to_vec(),
+        };
 
         let dsz = self.ds.dtype.size() as u64;
-        let vsz = counts.iter().product::<u64>() * dsz;
+        let vsz = out_counts.iter().product::<u64>() * dsz;
         let mut buf = Vec::with_capacity(vsz as usize);
         unsafe {
             buf.set_len(vsz as usize);
         }
         let mut buf_slice = &mut buf[..];
 
-        for (c, start, end) in self.ds.chunk_slices(indices, Some(&counts)) {
-            let start = (start * dsz) as usize;
-            let end = (end * dsz) as usize;
-            let slice_sz = end - start;
+        let key = self.ds.id();
 
-            if let Some(cache) = self.cache.get(&c.addr) {
-                buf_slice[..slice_sz].copy_from_slice(&cache[start..end]);
+        for (c, start, step, n) in self.ds.chunk_slices(indices, Some(counts), strides)? {
+            let run_sz = (n * dsz) as usize;
+
+            if let Some(cache) = self.cache.get(key, c.addr) {
+                copy_run(&cache, start, step, n, dsz, &mut buf_slice[..run_sz]);
             } else {
-                let mut cache: Vec<u8> = Vec::with_capacity(c.size as usize);
+                let mut chunk: Vec<u8> = Vec::with_capacity(c.size as usize);
                 unsafe {
-                    cache.set_len(c.size as usize);
+                    chunk.set_len(c.size as usize);
                 }
 
                 self.fd.seek(SeekFrom::Start(c.addr))?;
-                self.fd.read_exact(&mut cache)?;
+                self.fd.read_exact(&mut chunk)?;
 
-                let cache = if self.ds.shuffle {
-                    filters::shuffle::unshuffle_sized(&cache, dsz as usize)
-                } else {
-                    cache
-                };
+                let decompressed_size =
+                    self.ds.chunk_shape.iter().product::<u64>() as usize * dsz as usize;
+                let chunk =
+                    filters::decode(&self.ds.filters, chunk, dsz as usize, decompressed_size)?;
 
-                buf_slice[..slice_sz].copy_from_slice(&cache[start..end]);
-                self.cache.put(c.addr, cache);
+                let chunk = self.cache.put(key, c.addr, chunk);
+                copy_run(&chunk, start, step, n, dsz, &mut buf_slice[..run_sz]);
             }
 
-            buf_slice = &mut buf_slice[slice_sz..];
+            buf_slice = &mut buf_slice[run_sz..];
         }
 
         Ok(buf)
@@ -84,6 +98,7 @@ impl<'a> DatasetReader<'a> {
         &mut self,
         indices: Option<&[u64]>,
         counts: Option<&[u64]>,
+        strides: Option<&[u64]>,
     ) -> Result<Vec<T>, anyhow::Error>
     where
         T: FromByteVec,
@@ -92,7 +107,7 @@ impl<'a> DatasetReader<'a> {
         // TODO: use as_slice_of() to avoid copy, or possible values_to(&mut buf) so that
         //       caller keeps ownership of slice too.
 
-        let mut values = self.read(indices, counts)?.into_vec_of::<T>()?;
+        let mut values = self.read(indices, counts, strides)?.into_vec_of::<T>()?;
 
         use hdf5_sys::h5t::H5T_order_t::*;
         let order: Order = match self.ds.order {
@@ -105,6 +120,67 @@ impl<'a> DatasetReader<'a> {
 
         Ok(values)
     }
+
+    /// Like [`DatasetReader::values`], but returned as an arrow2
+    /// [`arrow2::array::PrimitiveArray`] that takes ownership of the same
+    /// `Vec<T>` buffer `values` returns (`PrimitiveArray::from_vec` wraps it
+    /// without copying), with the byte-order conversion done in place on
+    /// that buffer. The `read` call still copies the data out of the chunks
+    /// into this buffer, so this is not a copy-free path from disk. The
+    /// Python `read_arrow_column`/`column()` path shares this buffer across
+    /// the Arrow C Data Interface instead of handing back a fresh numpy
+    /// array.
+    #[cfg(feature = "arrow")]
+    pub fn values_arrow<T>(
+        &mut self,
+        indices: Option<&[u64]>,
+        counts: Option<&[u64]>,
+        strides: Option<&[u64]>,
+    ) -> Result<arrow2::array::PrimitiveArray<T>, anyhow::Error>
+    where
+        T: arrow2::types::NativeType + FromByteVec,
+        [T]: ToNative,
+    {
+        let mut values = self.read(indices, counts, strides)?.into_vec_of::<T>()?;
+
+        use hdf5_sys::h5t::H5T_order_t::*;
+        let order: Order = match self.ds.order {
+            H5T_ORDER_BE => Order::BE,
+            H5T_ORDER_LE => Order::LE,
+            _ => unimplemented!(),
+        };
+
+        values.to_native(order);
+
+        Ok(arrow2::array::PrimitiveArray::from_vec(values))
+    }
+
+    /// Like [`DatasetReader::values`], but reshaped by `counts`/`strides`
+    /// (the whole dataset shape when `counts` is `None`) into an
+    /// [`ndarray::ArrayD`] instead of a flat `Vec<T>`.
+    #[cfg(feature = "ndarray")]
+    pub fn values_ndarray<T>(
+        &mut self,
+        indices: Option<&[u64]>,
+        counts: Option<&[u64]>,
+        strides: Option<&[u64]>,
+    ) -> Result<ndarray::ArrayD<T>, anyhow::Error>
+    where
+        T: FromByteVec,
+        [T]: ToNative,
+    {
+        let counts = counts.unwrap_or_else(|| self.ds.shape.as_slice());
+        let shape: Vec<usize> = match strides {
+            Some(strides) => crate::idx::output_counts(counts, strides),
+            None => counts.to_vec(),
+        }
+        .into_iter()
+        .map(|d| d as usize)
+        .collect();
+
+        let values = self.values::<T>(indices, Some(counts), strides)?;
+        Ok(ndarray::ArrayD::from_shape_vec(shape, values)?)
+    }
 }
 
 #[cfg(test)]
@@ -117,7 +193,7 @@ mod tests {
         let i = Index::index("tests/data/t_float.h5").unwrap();
         let mut r = DatasetReader::with_dataset(i.dataset("d32_1").unwrap(), i.path()).unwrap();
 
-        let vs = r.values::<f32>(None, None).unwrap();
+        let vs = r.values::<f32>(None, None, None).unwrap();
 
         let h = hdf5::File::open(i.path()).unwrap();
         let hvs = h.dataset("d32_1").unwrap().read_raw::<f32>().unwrap();
@@ -131,7 +207,7 @@ mod tests {
         let mut r =
             DatasetReader::with_dataset(i.dataset("d_4_chunks").unwrap(), i.path()).unwrap();
 
-        let vs = r.values::<f32>(None, None).unwrap();
+        let vs = r.values::<f32>(None, None, None).unwrap();
 
         let h = hdf5::File::open(i.path()).unwrap();
         let hvs = h.dataset("d_4_chunks").unwrap().read_raw::<f32>().unwrap();
@@ -139,13 +215,30 @@ mod tests {
         assert_eq!(vs, hvs);
     }
 
+    #[test]
+    fn read_chunked_1d_strided() {
+        // `ds[::2]`: every other element, crossing chunk boundaries at
+        // offsets that are not themselves even multiples of the stride.
+        let i = Index::index("tests/data/chunked_oneD.h5").unwrap();
+        let mut r =
+            DatasetReader::with_dataset(i.dataset("d_4_chunks").unwrap(), i.path()).unwrap();
+
+        let vs = r.values::<f32>(None, None, Some(&[2])).unwrap();
+
+        let h = hdf5::File::open(i.path()).unwrap();
+        let hvs = h.dataset("d_4_chunks").unwrap().read_raw::<f32>().unwrap();
+        let expected: Vec<f32> = hvs.iter().step_by(2).cloned().collect();
+
+        assert_eq!(vs, expected);
+    }
+
     #[test]
     fn read_chunked_2d() {
         let i = Index::index("tests/data/chunked_twoD.h5").unwrap();
         let mut r =
             DatasetReader::with_dataset(i.dataset("d_4_chunks").unwrap(), i.path()).unwrap();
 
-        let vs = r.values::<f32>(None, None).unwrap();
+        let vs = r.values::<f32>(None, None, None).unwrap();
 
         let h = hdf5::File::open(i.path()).unwrap();
         let hvs = h.dataset("d_4_chunks").unwrap().read_raw::<f32>().unwrap();
@@ -153,6 +246,51 @@ mod tests {
         assert_eq!(vs, hvs);
     }
 
+    #[test]
+    fn shared_cache_across_readers() {
+        use crate::reader::SharedCache;
+
+        let i = Index::index("tests/data/chunked_twoD.h5").unwrap();
+        let ds = i.dataset("d_4_chunks").unwrap();
+        let cache = SharedCache::new(DEFAULT_CACHE_BYTES);
+
+        let mut r1 = DatasetReader::with_cache(ds, i.path(), cache.clone()).unwrap();
+        let mut r2 = DatasetReader::with_cache(ds, i.path(), cache).unwrap();
+
+        let vs1 = r1.values::<f32>(None, None, None).unwrap();
+        // Served entirely out of the cache `r1` just populated.
+        let vs2 = r2.values::<f32>(None, None, None).unwrap();
+
+        assert_eq!(vs1, vs2);
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn read_values_arrow_matches_values() {
+        let i = Index::index("tests/data/chunked_twoD.h5").unwrap();
+        let mut r =
+            DatasetReader::with_dataset(i.dataset("d_4_chunks").unwrap(), i.path()).unwrap();
+
+        let vs = r.values::<f32>(None, None, None).unwrap();
+        let arr = r.values_arrow::<f32>(None, None, None).unwrap();
+
+        assert_eq!(&arr.values()[..], vs.as_slice());
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn read_values_ndarray_matches_values() {
+        let i = Index::index("tests/data/chunked_twoD.h5").unwrap();
+        let ds = i.dataset("d_4_chunks").unwrap();
+        let mut r = DatasetReader::with_dataset(ds, i.path()).unwrap();
+
+        let vs = r.values::<f32>(None, None, None).unwrap();
+        let arr = r.values_ndarray::<f32>(None, None, None).unwrap();
+
+        assert_eq!(arr.shape(), ds.shape().iter().map(|&d| d as usize).collect::<Vec<_>>());
+        assert_eq!(arr.iter().cloned().collect::<Vec<_>>(), vs);
+    }
+
     #[test]
     fn read_chunked_shuffled_2d() {
         let i = Index::index("tests/data/dmrpp/chunked/chunked_shuffled_twoD.h5").unwrap();
@@ -160,7 +298,7 @@ mod tests {
             DatasetReader::with_dataset(i.dataset("d_4_shuffled_chunks").unwrap(), i.path())
                 .unwrap();
 
-        let vs = r.values::<f32>(None, None).unwrap();
+        let vs = r.values::<f32>(None, None, None).unwrap();
 
         let h = hdf5::File::open(i.path()).unwrap();
         let hvs = h
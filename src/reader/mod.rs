@@ -0,0 +1,31 @@
+//! Reading values out of a dataset using its chunk index, without going
+//! through the HDF5 library.
+
+pub mod cache;
+pub mod par;
+pub mod shared_cache;
+
+pub use cache::DatasetReader;
+pub use par::ParDatasetReader;
+pub use shared_cache::{SharedCache, DEFAULT_CACHE_BYTES};
+
+/// Copy `n` elements of `dsz` bytes each, `step` elements apart starting at
+/// the chunk-local element offset `start`, from `src` into `dst`. `step == 1`
+/// is the common case of a contiguous run, handled with a single
+/// `copy_from_slice`; otherwise each element is copied individually.
+pub(crate) fn copy_run(src: &[u8], start: u64, step: u64, n: u64, dsz: u64, dst: &mut [u8]) {
+    let start = (start * dsz) as usize;
+    let dsz = dsz as usize;
+
+    if step == 1 {
+        let len = n as usize * dsz;
+        dst.copy_from_slice(&src[start..start + len]);
+    } else {
+        let step = (step * dsz) as usize;
+
+        for k in 0..n as usize {
+            let s = start + k * step;
+            dst[k * dsz..(k + 1) * dsz].copy_from_slice(&src[s..s + dsz]);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! A work-stealing reader: the chunks needed to satisfy a read are
+//! partitioned across a thread pool, each thread pulling its own chunks off
+//! disk and decompressing them independently.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+
+use crate::filters;
+use crate::filters::byteorder::{Order, ToNative};
+use crate::idx::Dataset;
+use crate::reader::copy_run;
+use crate::reader::{SharedCache, DEFAULT_CACHE_BYTES};
+
+pub struct ParDatasetReader<'a> {
+    ds: &'a Dataset,
+    path: PathBuf,
+    cache: SharedCache,
+}
+
+/// A raw pointer into the output buffer, `Send`/`Sync` because the caller
+/// only ever hands out disjoint byte ranges of it to each worker.
+struct OutBuf(*mut u8);
+unsafe impl Send for OutBuf {}
+unsafe impl Sync for OutBuf {}
+
+impl<'a> ParDatasetReader<'a> {
+    pub fn with_dataset<P>(ds: &'a Dataset, p: P) -> Result<ParDatasetReader<'a>, anyhow::Error>
+    where
+        P: AsRef<Path>,
+    {
+        ParDatasetReader::with_cache(ds, p, SharedCache::new(DEFAULT_CACHE_BYTES))
+    }
+
+    /// Like [`ParDatasetReader::with_dataset`], but consulting and
+    /// populating `cache` instead of a private one — share it with other
+    /// readers (including [`crate::reader::DatasetReader`]) so the threads
+    /// partitioning a read hit a common warm cache rather than each
+    /// decompressing its own copy of a chunk.
+    pub fn with_cache<P>(
+        ds: &'a Dataset,
+        p: P,
+        cache: SharedCache,
+    ) -> Result<ParDatasetReader<'a>, anyhow::Error>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(ParDatasetReader {
+            ds,
+            path: p.as_ref().to_path_buf(),
+            cache,
+        })
+    }
+
+    /// Read the requested hyperslab directly into `dst`, partitioning the
+    /// chunks that cover it across the global rayon thread pool. `dst` must
+    /// be sized for `output_counts(counts, strides)` elements.
+    pub fn read_to(
+        &self,
+        indices: Option<&[u64]>,
+        counts: Option<&[u64]>,
+        strides: Option<&[u64]>,
+        dst: &mut [u8],
+    ) -> Result<(), anyhow::Error> {
+        let dsz = self.ds.dtype().size() as u64;
+
+        // Turn the lazy run iterator into owned work-items up front so they
+        // can be handed out to worker threads.
+        let runs: Vec<_> = self
+            .ds
+            .chunk_slices(indices, counts, strides)?
+            .scan(0u64, |out_off, (c, start, step, n)| {
+                let len = n * dsz;
+                let item = (c.clone(), start, step, len, *out_off);
+                *out_off += len;
+                Some(item)
+            })
+            .collect();
+
+        let out = OutBuf(dst.as_mut_ptr());
+        let path = &self.path;
+        let chunk_filters = self.ds.filters();
+        let decompressed_size = self.ds.chunk_shape().iter().product::<u64>() * dsz;
+        let cache = &self.cache;
+        let key = self.ds.id();
+
+        runs.into_par_iter().try_for_each(
+            |(chunk, start, step, len, out_off)| -> Result<(), anyhow::Error> {
+                let buf = if let Some(buf) = cache.get(key, chunk.addr) {
+                    buf
+                } else {
+                    let mut fd = File::open(path)?;
+                    let mut buf = vec![0u8; chunk.size as usize];
+                    fd.seek(SeekFrom::Start(chunk.addr))?;
+                    fd.read_exact(&mut buf)?;
+
+                    let buf = filters::decode(
+                        chunk_filters,
+                        buf,
+                        dsz as usize,
+                        decompressed_size as usize,
+                    )?;
+
+                    cache.put(key, chunk.addr, buf)
+                };
+
+                let out = &out;
+                unsafe {
+                    let dst =
+                        std::slice::from_raw_parts_mut(out.0.add(out_off as usize), len as usize);
+                    copy_run(&buf, start, step, len / dsz, dsz, dst);
+                }
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+
+    pub fn values_to_par<T>(
+        &self,
+        indices: Option<&[u64]>,
+        counts: Option<&[u64]>,
+        strides: Option<&[u64]>,
+        dst: &mut [T],
+    ) -> Result<(), anyhow::Error>
+    where
+        T: Copy,
+        [T]: ToNative,
+    {
+        let dsz = self.ds.dtype().size() as u64;
+        let nbytes = dst.len() as u64 * dsz;
+
+        let bytes =
+            unsafe { std::slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u8, nbytes as usize) };
+
+        self.read_to(indices, counts, strides, bytes)?;
+
+        use hdf5_sys::h5t::H5T_order_t::*;
+        let order = match self.ds.order() {
+            H5T_ORDER_BE => Order::BE,
+            H5T_ORDER_LE => Order::LE,
+            _ => unimplemented!(),
+        };
+
+        dst.to_native(order);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+//! A chunk cache that can be shared across several readers and threads.
+
+use std::sync::{Arc, Mutex};
+
+use lru::LruCache;
+
+/// Default byte budget for a [`SharedCache`] when none is given explicitly.
+pub const DEFAULT_CACHE_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Number of independent shards a [`SharedCache`] splits its budget and
+/// locking across. [`crate::reader::ParDatasetReader::read_to`] fans a
+/// single read out across the chunks of one dataset on the rayon thread
+/// pool; with a single lock, every worker's cache lookup/insert would
+/// serialize behind it regardless of how many chunks are in flight. Sharding
+/// by chunk address spreads that contention across shards instead.
+const SHARDS: usize = 8;
+
+/// Identifies a single chunk across every dataset sharing a [`SharedCache`]:
+/// a dataset's process-wide unique [`crate::idx::Dataset`] id (stable for
+/// the life of the process, unlike its address, which a `Dataset` freed and
+/// reallocated elsewhere could reuse) paired with the chunk's on-disk
+/// address.
+type Key = (u64, u64);
+
+struct Inner {
+    lru: LruCache<Key, Arc<Vec<u8>>>,
+    bytes: u64,
+    budget: u64,
+}
+
+impl Inner {
+    fn get(&mut self, key: &Key) -> Option<Arc<Vec<u8>>> {
+        self.lru.get(key).cloned()
+    }
+
+    fn put(&mut self, key: Key, chunk: Arc<Vec<u8>>) {
+        // Two readers can race to decompress the same chunk and both call
+        // `put`; only count the bytes this insertion actually adds, or a
+        // replaced entry (or a repeated race) would be double-counted and
+        // `bytes` would drift above the cache's real footprint.
+        if let Some(replaced) = self.lru.put(key, chunk.clone()) {
+            self.bytes -= replaced.len() as u64;
+        }
+        self.bytes += chunk.len() as u64;
+
+        // Always keep the entry we just inserted, even if it alone busts the
+        // budget.
+        while self.bytes > self.budget && self.lru.len() > 1 {
+            if let Some((_, evicted)) = self.lru.pop_lru() {
+                self.bytes -= evicted.len() as u64;
+            }
+        }
+    }
+}
+
+/// A cache of decompressed chunks, keyed by `(dataset, chunk address)` and
+/// shareable between readers: concurrent/parallel reads over the same file,
+/// or several [`crate::reader::DatasetReader`]s built from the same
+/// [`crate::idx::Index`], consult and populate the same cache rather than
+/// each keeping a private, cold one.
+///
+/// Capacity is a byte budget rather than an entry count, so a single chunk
+/// larger than the budget is still cached — it is simply the only entry kept
+/// — instead of collapsing to zero capacity. The budget is split evenly
+/// across the cache's shards, so it is enforced per shard rather than
+/// globally: with a small budget and many shards, the effective total
+/// capacity can run somewhat ahead of the configured one (each shard keeps
+/// at least one entry), and eviction decisions only ever compare entries
+/// that landed in the same shard.
+#[derive(Clone)]
+pub struct SharedCache {
+    shards: Arc<Vec<Mutex<Inner>>>,
+}
+
+impl SharedCache {
+    /// A cache that evicts its least-recently-used chunks once more than
+    /// `byte_budget` bytes of decompressed data are held.
+    pub fn new(byte_budget: u64) -> SharedCache {
+        let per_shard_budget = (byte_budget / SHARDS as u64).max(1);
+        let shards = (0..SHARDS)
+            .map(|_| {
+                Mutex::new(Inner {
+                    lru: LruCache::unbounded(),
+                    bytes: 0,
+                    budget: per_shard_budget,
+                })
+            })
+            .collect();
+
+        SharedCache {
+            shards: Arc::new(shards),
+        }
+    }
+
+    fn shard(&self, addr: u64) -> &Mutex<Inner> {
+        &self.shards[(addr as usize) % self.shards.len()]
+    }
+
+    pub(crate) fn get(&self, dataset: u64, addr: u64) -> Option<Arc<Vec<u8>>> {
+        self.shard(addr).lock().unwrap().get(&(dataset, addr))
+    }
+
+    pub(crate) fn put(&self, dataset: u64, addr: u64, chunk: Vec<u8>) -> Arc<Vec<u8>> {
+        let chunk = Arc::new(chunk);
+        self.shard(addr)
+            .lock()
+            .unwrap()
+            .put((dataset, addr), chunk.clone());
+
+        chunk
+    }
+}
+
+impl Default for SharedCache {
+    fn default() -> SharedCache {
+        SharedCache::new(DEFAULT_CACHE_BYTES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_put_roundtrip() {
+        let cache = SharedCache::new(1024);
+
+        assert!(cache.get(0, 0).is_none());
+
+        let put = cache.put(0, 0, vec![1, 2, 3]);
+        let got = cache.get(0, 0).unwrap();
+        assert_eq!(*put, *got);
+        assert_eq!(*got, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn distinct_datasets_do_not_collide() {
+        let cache = SharedCache::new(1024);
+
+        cache.put(0, 42, vec![1]);
+        cache.put(1, 42, vec![2]);
+
+        assert_eq!(*cache.get(0, 42).unwrap(), vec![1]);
+        assert_eq!(*cache.get(1, 42).unwrap(), vec![2]);
+    }
+
+    #[test]
+    fn evicts_least_recently_used_once_over_budget() {
+        // A budget of `2 * SHARDS` so the shard these three addresses land
+        // in (they are all congruent mod `SHARDS`) gets a 2-byte budget of
+        // its own, same as the pre-sharding version of this test.
+        let cache = SharedCache::new(2 * SHARDS as u64);
+
+        cache.put(0, 0, vec![0; 1]);
+        cache.put(0, SHARDS as u64, vec![0; 1]);
+        assert!(cache.get(0, 0).is_some());
+
+        // Pushes this shard 1 byte over its budget; (0, SHARDS) is its LRU
+        // entry.
+        cache.put(0, 2 * SHARDS as u64, vec![0; 1]);
+
+        assert!(cache.get(0, SHARDS as u64).is_none());
+        assert!(cache.get(0, 0).is_some());
+        assert!(cache.get(0, 2 * SHARDS as u64).is_some());
+    }
+
+    #[test]
+    fn repeated_put_at_same_key_does_not_inflate_accounted_bytes() {
+        // Simulates two readers racing to decompress the same chunk and
+        // both calling `put`: the second `put` replaces rather than adds,
+        // so it must not count its bytes twice against the budget. A budget
+        // of `2 * SHARDS` gives the shard addresses 0 and `SHARDS` share a
+        // 2-byte budget of its own.
+        let cache = SharedCache::new(2 * SHARDS as u64);
+
+        cache.put(0, 0, vec![0; 1]);
+        cache.put(0, 0, vec![0; 1]);
+
+        // If the replaced entry's bytes were not first subtracted, this
+        // third, distinct entry in the same shard would trigger an eviction
+        // of (0, 0) even though the shard only actually holds 2 bytes.
+        cache.put(0, SHARDS as u64, vec![0; 1]);
+
+        assert!(cache.get(0, 0).is_some());
+        assert!(cache.get(0, SHARDS as u64).is_some());
+    }
+
+    #[test]
+    fn keeps_a_single_entry_larger_than_the_budget() {
+        let cache = SharedCache::new(1);
+
+        let put = cache.put(0, 0, vec![0; 8]);
+        assert_eq!(put.len(), 8);
+        assert!(cache.get(0, 0).is_some());
+    }
+}